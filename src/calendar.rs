@@ -0,0 +1,87 @@
+use std::collections::VecDeque;
+
+use leptos::*;
+use rustc_hash::FxHashMap;
+
+use crate::Record;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+const MAX_WEEKS: i64 = 53;
+
+#[component]
+pub fn ActivityCalendar(cx: Scope, history: ReadSignal<VecDeque<Record>>) -> impl IntoView {
+    let offset = js_sys::Date::new_0().get_timezone_offset() as i64 * 60_000;
+    let day_of = move |timestamp: u64| (timestamp as i64 - offset).div_euclid(MILLIS_PER_DAY);
+
+    let counts = create_memo(cx, move |_| {
+        let mut counts: FxHashMap<i64, u64> = FxHashMap::default();
+        history.with(|history| {
+            for record in history.iter().filter(|record| record.timestamp() != 0) {
+                *counts.entry(day_of(record.timestamp())).or_default() += 1;
+            }
+        });
+        counts
+    });
+
+    let days = move || {
+        let counts = counts();
+        let Some(&first) = counts.keys().min() else {
+            return Vec::new();
+        };
+
+        let today = day_of(js_sys::Date::now() as u64);
+        let start = first.max(today - MAX_WEEKS * 7 + 1);
+        let start = start - weekday(start);
+        let max = counts.values().copied().max().unwrap_or(1);
+
+        (start..=today)
+            .map(|day| {
+                let count = counts.get(&day).copied().unwrap_or(0);
+                (day, count, (count * 4).div_ceil(max))
+            })
+            .collect()
+    };
+
+    view! { cx,
+        <div class="ActivityCalendar container">
+            {move || counts().is_empty().then(|| view! { cx, <span>"No dated runs yet."</span> })}
+            <div class="ActivityCalendar grid">
+                <For
+                    each=days
+                    key=|&(day, count, _)| (day, count)
+                    view=move |cx, (day, count, level)| {
+                        let (year, month, date) = civil_from_days(day);
+                        let runs = if count == 1 { "run" } else { "runs" };
+                        view! { cx,
+                            <div
+                                class="ActivityCalendar day"
+                                style=("--level", level.to_string())
+                                title=format!("{count} {runs} on {year:04}-{month:02}-{date:02}")
+                            />
+                        }
+                    }
+                />
+            </div>
+        </div>
+    }
+}
+
+/// Day of the week for a count of days since the Unix epoch, with Sunday as `0`.
+#[inline]
+const fn weekday(days: i64) -> i64 {
+    (days + 4).rem_euclid(7)
+}
+
+/// Converts a count of days since the Unix epoch into a proleptic Gregorian `(year, month, day)`.
+const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,157 @@
+use leptos::*;
+
+use crate::{number::Locale, Record};
+
+const WIDTH: f64 = 600.0;
+const HEIGHT: f64 = 240.0;
+/// Room around the plot for the axis labels.
+const LEFT: f64 = 48.0;
+const RIGHT: f64 = 16.0;
+const TOP: f64 = 12.0;
+const BOTTOM: f64 = 36.0;
+/// Gridlines drawn across the score axis, besides the one along its bottom.
+const Y_TICKS: usize = 4;
+
+/// Where each run's point sits in the chart's coordinates, along with the lowest and highest
+/// scores on the score axis.
+struct Layout {
+    points: Vec<(f64, f64)>,
+    low: f64,
+    high: f64,
+}
+
+impl Layout {
+    fn new(runs: &[Record]) -> Self {
+        let scores = runs.iter().map(|run| run.score() as f64);
+        let mut low = scores.clone().fold(f64::INFINITY, f64::min);
+        let mut high = scores.clone().fold(f64::NEG_INFINITY, f64::max);
+        // Give a flat line some room above and below rather than dividing by zero.
+        if high - low < 1.0 {
+            low = (low - 1.0).max(0.0);
+            high = low + 2.0;
+        }
+
+        let width = WIDTH - LEFT - RIGHT;
+        let height = HEIGHT - TOP - BOTTOM;
+        let points = scores
+            .enumerate()
+            .map(|(idx, score)| {
+                let x = match runs.len() {
+                    1 => LEFT + width / 2.0,
+                    len => LEFT + width * idx as f64 / (len - 1) as f64,
+                };
+                (x, TOP + height * (high - score) / (high - low))
+            })
+            .collect();
+
+        Self { points, low, high }
+    }
+}
+
+/// A line chart of score over `runs`, oldest first, with the details of the run under the
+/// pointer shown beside its point. The chart scales with the width of the page.
+#[component]
+pub fn ScoreChart(
+    cx: Scope,
+    #[prop(into)] runs: Signal<Vec<Record>>,
+    #[prop(into)] locale: Signal<Locale>,
+) -> impl IntoView {
+    let (hovered, set_hovered) = create_signal(cx, None::<usize>);
+    let layout = create_memo(cx, move |_| runs.with(|runs| Layout::new(runs).points));
+
+    let chart = move || {
+        runs.with(|runs| {
+            if runs.is_empty() {
+                return None;
+            }
+
+            let Layout { points, low, high } = Layout::new(runs);
+            let decimals = if high - low < Y_TICKS as f64 { 1 } else { 0 };
+            let gridlines = (0..=Y_TICKS)
+                .map(|tick| {
+                    let value = low + (high - low) * tick as f64 / Y_TICKS as f64;
+                    let y = HEIGHT - BOTTOM - (HEIGHT - TOP - BOTTOM) * tick as f64 / Y_TICKS as f64;
+                    view! { cx,
+                        <line class="ScoreChart gridline" x1=LEFT x2=WIDTH - RIGHT y1=y y2=y />
+                        <text class="ScoreChart tick" x=LEFT - 6.0 y=y text-anchor="end" dominant-baseline="middle">
+                            {locale.get().decimal(value, decimals)}
+                        </text>
+                    }
+                })
+                .collect::<Vec<_>>();
+            let line = points
+                .iter()
+                .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let dots = points
+                .iter()
+                .enumerate()
+                .map(|(idx, &(x, y))| {
+                    view! { cx,
+                        <circle
+                            class="ScoreChart point"
+                            cx=x
+                            cy=y
+                            r="4"
+                            on:mouseenter=move |_| set_hovered(Some(idx))
+                            on:mouseleave=move |_| set_hovered(None)
+                        />
+                    }
+                })
+                .collect::<Vec<_>>();
+            let last = runs.len();
+
+            Some(view! { cx,
+                <svg class="ScoreChart" viewBox=format!("0 0 {WIDTH} {HEIGHT}") role="img" aria-label="Score by run">
+                    {gridlines}
+                    <line class="ScoreChart axis" x1=LEFT x2=LEFT y1=TOP y2=HEIGHT - BOTTOM />
+                    <line class="ScoreChart axis" x1=LEFT x2=WIDTH - RIGHT y1=HEIGHT - BOTTOM y2=HEIGHT - BOTTOM />
+                    <text class="ScoreChart tick" x=points[0].0 y=HEIGHT - BOTTOM + 14.0 text-anchor="middle">"1"</text>
+                    {(last > 1).then(|| view! { cx,
+                        <text class="ScoreChart tick" x=points[last - 1].0 y=HEIGHT - BOTTOM + 14.0 text-anchor="middle">
+                            {locale.get().integer(last as u64)}
+                        </text>
+                    })}
+                    <text class="ScoreChart label" x=LEFT + (WIDTH - LEFT - RIGHT) / 2.0 y=HEIGHT - 4.0 text-anchor="middle">"Run"</text>
+                    <text class="ScoreChart label" x="12" y=TOP + (HEIGHT - TOP - BOTTOM) / 2.0 text-anchor="middle" transform=format!("rotate(-90 12 {})", TOP + (HEIGHT - TOP - BOTTOM) / 2.0)>
+                        "Score"
+                    </text>
+                    <polyline class="ScoreChart line" points=line />
+                    {dots}
+                </svg>
+            })
+        })
+    };
+
+    let tooltip = move || {
+        let idx = hovered()?;
+        let record = runs.with(|runs| runs.get(idx).copied())?;
+        let (x, y) = layout.with(|points| points.get(idx).copied())?;
+        Some(view! { cx,
+            <div
+                class="ScoreChart tooltip"
+                style=("left", format!("{}%", x / WIDTH * 100.0))
+                style=("top", format!("{}%", y / HEIGHT * 100.0))
+            >
+                {format!(
+                    "Run {}: {} in {}s ({}/s), {}×{}",
+                    locale.get().integer(record.position()),
+                    locale.get().integer(record.score()),
+                    locale.get().decimal(record.seconds(), 2),
+                    locale.get().rate(&record),
+                    record.rows(),
+                    record.columns()
+                )}
+            </div>
+        })
+    };
+
+    view! { cx,
+        <div class="ScoreChart container">
+            {move || runs.with(Vec::is_empty).then(|| view! { cx, <span>"No runs on this board yet."</span> })}
+            {chart}
+            {tooltip}
+        </div>
+    }
+}
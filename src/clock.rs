@@ -0,0 +1,101 @@
+use std::{
+    ops::{Add, Sub},
+    time::Duration,
+};
+
+use web_time::Instant;
+
+/// A moment read from both the monotonic clock and the wall clock. Some embedded webviews have
+/// a performance clock that stalls or runs backwards, so durations between stamps fall back to
+/// the wall clock when the monotonic reading can't be trusted.
+#[derive(Clone, Copy)]
+pub struct Stamp {
+    instant: Instant,
+    /// Milliseconds since the Unix epoch.
+    wall: f64,
+}
+
+impl Stamp {
+    pub fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            wall: js_sys::Date::now(),
+        }
+    }
+
+    pub const fn instant(self) -> Instant {
+        self.instant
+    }
+
+    /// Time from `earlier` to `self`. The monotonic clock is used unless it went backwards, or
+    /// stood still while the wall clock moved on, in which case the wall clock's reading is used.
+    /// Never negative, as a wall clock set backwards reads as no time at all.
+    pub fn since(self, earlier: Self) -> Duration {
+        let wall = Duration::from_secs_f64(((self.wall - earlier.wall) / 1000.0).max(0.0));
+        match self.instant.checked_duration_since(earlier.instant) {
+            Some(elapsed) if !elapsed.is_zero() || wall.is_zero() => elapsed,
+            _ => wall,
+        }
+    }
+}
+
+impl Sub for Stamp {
+    type Output = Duration;
+
+    fn sub(self, earlier: Self) -> Duration {
+        self.since(earlier)
+    }
+}
+
+impl Add<Duration> for Stamp {
+    type Output = Self;
+
+    fn add(self, duration: Duration) -> Self {
+        Self {
+            instant: self.instant + duration,
+            wall: self.wall + duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamp(instant: Instant, wall: f64) -> Stamp {
+        Stamp { instant, wall }
+    }
+
+    #[test]
+    fn working_clock_is_trusted() {
+        let start = Instant::now();
+        let earlier = stamp(start, 1000.0);
+        let later = stamp(start + Duration::from_millis(250), 1900.0);
+        assert_eq!(later - earlier, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn stalled_clock_falls_back_to_the_wall_clock() {
+        let start = Instant::now();
+        assert_eq!(
+            stamp(start, 1040.0) - stamp(start, 1000.0),
+            Duration::from_millis(40)
+        );
+    }
+
+    #[test]
+    fn backwards_clock_falls_back_to_the_wall_clock() {
+        let start = Instant::now();
+        let earlier = stamp(start + Duration::from_millis(500), 1000.0);
+        let later = stamp(start, 1120.0);
+        assert_eq!(later - earlier, Duration::from_millis(120));
+    }
+
+    #[test]
+    fn both_clocks_going_backwards_reads_as_no_time() {
+        let start = Instant::now();
+        let earlier = stamp(start + Duration::from_millis(500), 1000.0);
+        let later = stamp(start, 900.0);
+        assert_eq!(later - earlier, Duration::ZERO);
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{storage, Record};
+
+/// Upper bounds, in milliseconds, of the buckets intervals between hits are counted in. The last
+/// bucket holds everything slower.
+const BOUNDS: [u64; 7] = [50, 100, 150, 200, 300, 500, 1000];
+/// Hits that follow the previous one faster than this are beyond human reaction and movement.
+const HUMAN_LIMIT_MILLIS: u64 = 100;
+/// Share of intervals that may beat [`HUMAN_LIMIT_MILLIS`], to allow for two targets being hit
+/// with one sweep, before a run is flagged.
+const MAX_INHUMAN_SHARE: f64 = 0.1;
+
+/// A histogram of the intervals between consecutive hits of a run, small enough to carry in a
+/// share link so the receiver can check the run's timings are humanly possible.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Digest([u16; BOUNDS.len() + 1]);
+
+/// Digests of runs, keyed by the run's timestamp.
+pub type Digests = BTreeMap<u64, Digest>;
+
+impl Digest {
+    pub fn add(&mut self, interval: Duration) {
+        let millis = interval.as_millis();
+        let idx = BOUNDS
+            .iter()
+            .position(|&bound| millis < bound as u128)
+            .unwrap_or(BOUNDS.len());
+        self.0[idx] = self.0[idx].saturating_add(1);
+    }
+
+    fn count(&self) -> u64 {
+        self.0.iter().map(|&count| count as u64).sum()
+    }
+
+    /// Why `record` could not have been played with these timings, if it couldn't.
+    pub fn verify(&self, record: &Record) -> Result<(), &'static str> {
+        if self.count() != record.score().saturating_sub(1) {
+            return Err("the timings don't match the score");
+        }
+
+        let shortest: u64 = self
+            .0
+            .iter()
+            .zip(std::iter::once(0).chain(BOUNDS))
+            .map(|(&count, lower)| count as u64 * lower)
+            .sum();
+        if shortest as u128 > record.millis() {
+            return Err("the timings add up to more than the run's duration");
+        }
+
+        let inhuman: u64 = BOUNDS
+            .iter()
+            .zip(self.0)
+            .take_while(|&(&bound, _)| bound <= HUMAN_LIMIT_MILLIS)
+            .map(|(_, count)| count as u64)
+            .sum();
+        if inhuman as f64 > self.count() as f64 * MAX_INHUMAN_SHARE {
+            return Err("too many hits came faster than humanly possible");
+        }
+
+        Ok(())
+    }
+
+    /// Encodes the digest as its bucket counts separated by dots.
+    pub fn to_str(self) -> String {
+        self.0.map(|count| count.to_string()).join(".")
+    }
+
+    /// Inverse of [`Digest::to_str`].
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        let mut digest = Self::default();
+        let mut counts = s.split('.');
+        for slot in &mut digest.0 {
+            *slot = counts.next()?.parse().ok()?;
+        }
+        counts.next().is_none().then_some(digest)
+    }
+}
+
+/// Remembers `digest` for the run that ended at `timestamp`.
+pub fn record(timestamp: u64, digest: Digest) {
+    let mut digests: Digests = storage::get("digests").unwrap_or_default();
+    digests.insert(timestamp, digest);
+    let _ = storage::set("digests", digests);
+}
+
+/// The digest remembered for the run that ended at `timestamp`, if any.
+pub fn find(timestamp: u64) -> Option<Digest> {
+    let digests: Digests = storage::get("digests").ok()?;
+    digests.get(&timestamp).copied()
+}
+
+/// Forgets the digests of runs no longer in `history`, so they don't outlive the runs they were
+/// kept for.
+pub fn prune(history: &VecDeque<Record>) {
+    let Ok(mut digests) = storage::get::<Digests>("digests") else {
+        return;
+    };
+    let kept: BTreeSet<_> = history.iter().map(Record::timestamp).collect();
+    let before = digests.len();
+    digests.retain(|timestamp, _| kept.contains(timestamp));
+    if digests.is_empty() {
+        storage::delete("digests");
+    } else if digests.len() != before {
+        let _ = storage::set("digests", digests);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(intervals: &[u64]) -> Digest {
+        let mut digest = Digest::default();
+        for &millis in intervals {
+            digest.add(Duration::from_millis(millis));
+        }
+        digest
+    }
+
+    #[test]
+    fn plausible_timings_verify() {
+        let run = Record::new(1, 4, 2000, 3, 3);
+        assert_eq!(digest(&[400, 600, 900]).verify(&run), Ok(()));
+    }
+
+    #[test]
+    fn timings_longer_than_the_run_are_flagged() {
+        // Each interval is at least a second, so three can't fit in two.
+        let run = Record::new(1, 4, 2000, 3, 3);
+        assert_eq!(
+            digest(&[1500, 1500, 1500]).verify(&run),
+            Err("the timings add up to more than the run's duration")
+        );
+    }
+
+    #[test]
+    fn timings_for_another_score_are_flagged() {
+        let run = Record::new(1, 4, 2000, 3, 3);
+        assert_eq!(
+            digest(&[400, 600]).verify(&run),
+            Err("the timings don't match the score")
+        );
+        assert_eq!(
+            digest(&[400, 400, 400, 400]).verify(&run),
+            Err("the timings don't match the score")
+        );
+    }
+
+    #[test]
+    fn inhuman_timings_are_flagged() {
+        let run = Record::new(1, 4, 2000, 3, 3);
+        assert_eq!(
+            digest(&[20, 30, 40]).verify(&run),
+            Err("too many hits came faster than humanly possible")
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{
+    Blob, CanvasRenderingContext2d, File, FileReader, HtmlAnchorElement, HtmlCanvasElement, Url,
+};
+
+use crate::{number::Locale, record::SERIALIZED_LEN, storage, Record};
+
+const WIDTH: u32 = 480;
+const LINE_HEIGHT: u32 = 32;
+const PADDING: u32 = 24;
+
+/// Draws `title` followed by `lines` onto a canvas and downloads it as `filename`.
+pub fn save_image(filename: &str, title: &str, lines: &[String]) -> Result<(), JsValue> {
+    let canvas: HtmlCanvasElement = document().create_element("canvas")?.dyn_into()?;
+    canvas.set_width(WIDTH);
+    canvas.set_height(PADDING * 2 + LINE_HEIGHT * (lines.len() as u32 + 1));
+
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("2d context unavailable"))?
+        .dyn_into()?;
+
+    context.set_fill_style(&JsValue::from_str("white"));
+    context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+
+    context.set_fill_style(&JsValue::from_str("black"));
+    context.set_text_baseline("top");
+    context.set_font("bold 24px sans-serif");
+    context.fill_text(title, PADDING as f64, PADDING as f64)?;
+
+    context.set_font("18px sans-serif");
+    for (idx, line) in lines.iter().enumerate() {
+        let y = PADDING + LINE_HEIGHT * (idx as u32 + 1);
+        context.fill_text(line, PADDING as f64, y as f64)?;
+    }
+
+    download(filename, &canvas.to_data_url_with_type("image/png")?)
+}
+
+/// The browser and screen a run was played on, kept only on this device and only included in
+/// exports the player asks for.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Device {
+    user_agent: String,
+    screen_width: i32,
+    screen_height: i32,
+}
+
+impl Device {
+    pub fn current() -> Option<Self> {
+        let window = window();
+        let screen = window.screen().ok()?;
+        Some(Self {
+            user_agent: window.navigator().user_agent().ok()?,
+            screen_width: screen.width().ok()?,
+            screen_height: screen.height().ok()?,
+        })
+    }
+}
+
+/// Devices runs were played on, keyed by the run's timestamp.
+pub type Devices = BTreeMap<u64, Device>;
+
+/// Remembers the current device for the run that ended at `timestamp`.
+pub fn record_device(timestamp: u64) {
+    let Some(device) = Device::current() else {
+        return;
+    };
+    let mut devices: Devices = storage::get("devices").unwrap_or_default();
+    devices.insert(timestamp, device);
+    let _ = storage::set("devices", devices);
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    record: &'a Record,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device: Option<&'a Device>,
+}
+
+/// Downloads `history` as JSON, with the device each run was played on where it was recorded.
+pub fn save_json(filename: &str, history: &VecDeque<Record>) -> Result<(), JsValue> {
+    let devices: Devices = storage::get("devices").unwrap_or_default();
+    let entries: Vec<_> = history
+        .iter()
+        .map(|record| Entry {
+            record,
+            device: devices.get(&record.timestamp()),
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    let href = format!(
+        "data:application/json;charset=utf-8,{}",
+        js_sys::encode_uri_component(&json)
+    );
+    download(filename, &href)
+}
+
+/// Downloads every record in `history`, packed back to back with [`Record::to_bytes`].
+pub fn save_backup(filename: &str, history: &VecDeque<Record>) -> Result<(), JsValue> {
+    let bytes: Vec<u8> = history
+        .iter()
+        .flat_map(|record| record.to_bytes())
+        .collect();
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes.as_slice()));
+    let blob = Blob::new_with_u8_array_sequence(&parts)?;
+
+    let href = Url::create_object_url_with_blob(&blob)?;
+    download(filename, &href)?;
+    // Revoking straight away can cancel the download in some browsers.
+    set_timeout(
+        move || {
+            let _ = Url::revoke_object_url(&href);
+        },
+        Duration::from_secs(10),
+    );
+    Ok(())
+}
+
+/// Inverse of [`save_backup`], rejecting files that aren't a whole number of records or contain
+/// one that can't be decoded.
+pub fn parse_backup(bytes: &[u8]) -> Result<Vec<Record>, String> {
+    if !bytes.len().is_multiple_of(SERIALIZED_LEN) {
+        return Err(format!(
+            "{} bytes is not a whole number of {SERIALIZED_LEN}-byte records.",
+            bytes.len()
+        ));
+    }
+
+    bytes
+        .chunks(SERIALIZED_LEN)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            Record::try_from_bytes(chunk)
+                .map_err(|err| format!("Record {} is invalid, as {err}.", idx + 1))
+        })
+        .collect()
+}
+
+/// Reads `file` in full and passes its contents to `onload`.
+pub fn read_file<F>(file: &File, onload: F) -> Result<(), JsValue>
+where
+    F: FnOnce(Vec<u8>) + 'static,
+{
+    let reader = FileReader::new()?;
+    let target = reader.clone();
+    let onload = Closure::once(move || {
+        if let Ok(buffer) = target.result() {
+            onload(js_sys::Uint8Array::new(&buffer).to_vec());
+        }
+    });
+    reader.set_onload(Some(onload.as_ref().unchecked_ref()));
+    onload.forget();
+    reader.read_as_array_buffer(file)
+}
+
+/// Lays `records` out as a Markdown table with the same columns, rounding and separators as the
+/// history table.
+pub fn markdown_table<'a>(records: impl IntoIterator<Item = &'a Record>, locale: Locale) -> String {
+    let mut table = "| Pos | Score | Score/s | Seconds |\n|---:|---:|---:|---:|\n".to_owned();
+    for record in records {
+        table += &format!(
+            "| {} | {} | {} | {} |\n",
+            record.position(),
+            locale.integer(record.score()),
+            locale.rate(record),
+            locale.decimal(record.seconds(), 2)
+        );
+    }
+    table
+}
+
+/// Copies `text` to the clipboard. The Clipboard API is looked up by name, as web-sys only
+/// exposes it behind an unstable flag.
+pub fn copy_text(text: &str) -> Result<(), JsValue> {
+    let clipboard = js_sys::Reflect::get(&window().navigator(), &JsValue::from_str("clipboard"))?;
+    let write: js_sys::Function =
+        js_sys::Reflect::get(&clipboard, &JsValue::from_str("writeText"))?.dyn_into()?;
+    write.call1(&clipboard, &JsValue::from_str(text))?;
+    Ok(())
+}
+
+/// Triggers a download of `href` under the name `filename`.
+pub fn download(filename: &str, href: &str) -> Result<(), JsValue> {
+    let anchor: HtmlAnchorElement = document().create_element("a")?.dyn_into()?;
+    anchor.set_href(href);
+    anchor.set_download(filename);
+    anchor.click();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_follows_the_locale() {
+        let runs = [
+            Record::new(2, 1234, 61_500, 3, 3),
+            Record::new(1, 0, 0, 3, 3),
+        ];
+        assert_eq!(
+            markdown_table(&runs, Locale::COMMA),
+            "| Pos | Score | Score/s | Seconds |\n|---:|---:|---:|---:|\n\
+             | 2 | 1.234 | 20,07 | 61,50 |\n\
+             | 1 | 0 | -- | 0,00 |\n"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use serde::*;
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Mode {
+    /// Hit any of the active cells, each hit moving the target elsewhere.
+    #[default]
+    Classic,
+    /// Repeat back an ever-growing sequence of flashed cells.
+    Memory,
+    /// Hit a single target as soon as it appears after a random delay, over a fixed number of
+    /// trials. The score is the number of trials and the duration their summed reaction times.
+    Reaction,
+}
+
+impl Mode {
+    pub const OPTIONS: &'static [(Self, &'static str)] = &[
+        (Self::Classic, "Classic"),
+        (Self::Memory, "Memory"),
+        (Self::Reaction, "Reaction"),
+    ];
+
+    #[inline]
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Classic),
+            1 => Some(Self::Memory),
+            2 => Some(Self::Reaction),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        Self::OPTIONS
+            .iter()
+            .find(|(mode, _)| *mode == self)
+            .map_or("", |(_, name)| name)
+    }
+}
@@ -0,0 +1,130 @@
+use leptos::window;
+use serde::{Deserialize, Serialize};
+
+use crate::Record;
+
+/// The characters a locale writes numbers with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Locale {
+    /// Between groups of three digits, such as the `,` in `1,234`.
+    pub group: char,
+    /// Before the decimals, such as the `.` in `1.5`.
+    pub decimal: char,
+}
+
+impl Locale {
+    pub const POINT: Self = Self {
+        group: ',',
+        decimal: '.',
+    };
+    pub const COMMA: Self = Self {
+        group: '.',
+        decimal: ',',
+    };
+    pub const SPACE: Self = Self {
+        group: '\u{a0}',
+        decimal: ',',
+    };
+
+    /// The separators used for the BCP 47 language `tag`, such as `"de-DE"`. Languages this
+    /// doesn't know are written like English.
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.to_ascii_lowercase();
+        if tag.starts_with("de-ch") || tag.starts_with("de-li") {
+            return Self {
+                group: '’',
+                decimal: '.',
+            };
+        }
+
+        match tag.split(['-', '_']).next().unwrap_or_default() {
+            "da" | "de" | "el" | "es" | "hr" | "id" | "it" | "nl" | "pt" | "ro" | "sl" | "sr"
+            | "tr" | "vi" => Self::COMMA,
+            "bg" | "cs" | "et" | "fi" | "fr" | "hu" | "lt" | "lv" | "nb" | "nn" | "no" | "pl"
+            | "ru" | "sk" | "sv" | "uk" => Self::SPACE,
+            _ => Self::POINT,
+        }
+    }
+
+    /// The separators for the browser's preferred language, from `navigator.language`.
+    pub fn browser() -> Self {
+        window()
+            .navigator()
+            .language()
+            .map_or(Self::POINT, |tag| Self::from_tag(&tag))
+    }
+
+    /// `value` with its digits grouped in threes.
+    pub fn integer(self, value: u64) -> String {
+        self.group_digits(&value.to_string())
+    }
+
+    /// `value` rounded to `places` decimals, with the whole part grouped in threes.
+    pub fn decimal(self, value: f64, places: usize) -> String {
+        let text = format!("{:.places$}", value.abs());
+        let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+        let mut out = String::with_capacity(text.len() + text.len() / 3 + 1);
+        if value.is_sign_negative() && text.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+            out.push('-');
+        }
+        out.push_str(&self.group_digits(whole));
+        if !fraction.is_empty() {
+            out.push(self.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// [`Record::rate_text`] written with these separators.
+    pub fn rate(self, record: &Record) -> String {
+        if record.micros() == 0 {
+            "--".to_owned()
+        } else {
+            self.decimal(record.rate(), 2)
+        }
+    }
+
+    fn group_digits(self, digits: &str) -> String {
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return digits.to_owned();
+        }
+
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+        for (idx, digit) in digits.chars().enumerate() {
+            if idx != 0 && (digits.len() - idx).is_multiple_of(3) {
+                out.push(self.group);
+            }
+            out.push(digit);
+        }
+        out
+    }
+}
+
+/// Which separators numbers are shown with, as chosen in the settings.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumberFormat {
+    /// Follow the browser's language.
+    #[default]
+    Browser,
+    Point,
+    Comma,
+    Space,
+}
+
+impl NumberFormat {
+    pub const OPTIONS: &'static [(Self, &'static str)] = &[
+        (Self::Browser, "Browser Default"),
+        (Self::Point, "1,234.56"),
+        (Self::Comma, "1.234,56"),
+        (Self::Space, "1 234,56"),
+    ];
+
+    pub fn locale(self) -> Locale {
+        match self {
+            Self::Browser => Locale::browser(),
+            Self::Point => Locale::POINT,
+            Self::Comma => Locale::COMMA,
+            Self::Space => Locale::SPACE,
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{spawn::Respawn, storage, Mode};
+
+const KEY: &str = "profiles";
+
+/// A named bundle of board and mode settings that can be switched to in one go.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+    pub active: usize,
+    pub mode: Mode,
+    pub lives: usize,
+    pub reaction_trials: usize,
+    pub min_distance: usize,
+    pub edge_bias: usize,
+    pub shrink_every: usize,
+    pub shrink_min: usize,
+    pub reveal_delay_min: usize,
+    pub reveal_delay_max: usize,
+    // Added after profiles were first saved, so older ones load with the settings' defaults.
+    #[serde(default)]
+    pub respawn: Respawn,
+    #[serde(default)]
+    pub in_order: bool,
+    #[serde(default)]
+    pub spacing: usize,
+    #[serde(default)]
+    pub sprint: usize,
+    #[serde(default)]
+    pub live_delay: usize,
+    #[serde(default)]
+    pub hit_cooldown: usize,
+}
+
+/// Lists the saved profiles with buttons to apply or delete each, and saves the current settings
+/// as a new one. `capture` builds a profile with the given name from the current settings and
+/// `apply` switches every setting to a profile's.
+#[component]
+pub fn Profiles<C, A>(cx: Scope, capture: C, apply: A) -> impl IntoView
+where
+    C: Fn(String) -> Profile + 'static,
+    A: Fn(Profile) + Copy + 'static,
+{
+    let (profiles, set_profiles) =
+        create_signal(cx, storage::get::<Vec<Profile>>(KEY).unwrap_or_default());
+    let (name, set_name) = create_signal(cx, String::new());
+
+    let save = move || {
+        let _ = storage::set(KEY, profiles());
+    };
+
+    let create = move |_| {
+        let name = name().trim().to_owned();
+        if name.is_empty() {
+            return;
+        }
+
+        let profile = capture(name.clone());
+        set_profiles.update(
+            |profiles| match profiles.iter_mut().find(|p| p.name == name) {
+                Some(existing) => *existing = profile,
+                None => profiles.push(profile),
+            },
+        );
+        set_name(String::new());
+        save();
+    };
+
+    view! { cx,
+        <details class="Profiles">
+            <summary>"Profiles"</summary>
+            <table>
+                <For
+                    each=profiles
+                    // Keyed by the whole profile, so saving over a name replaces its row rather than
+                    // leaving the old settings behind it.
+                    key=|profile| serde_json::to_string(profile).unwrap_or_default()
+                    view=move |cx, profile| {
+                        let name = profile.name.clone();
+                        let summary = format!(
+                            "{}×{}, {} active, {}",
+                            profile.rows,
+                            profile.columns,
+                            profile.active,
+                            profile.mode.name()
+                        );
+                        view! { cx,
+                            <tr>
+                                <td>{name.clone()}</td>
+                                <td>{summary}</td>
+                                <td><button on:click=move |_| apply(profile.clone())>"Apply"</button></td>
+                                <td>
+                                    <button on:click=move |_| {
+                                        set_profiles.update(|profiles| profiles.retain(|p| p.name != name));
+                                        save();
+                                    }>"Delete"</button>
+                                </td>
+                            </tr>
+                        }
+                    }
+                />
+            </table>
+            <input
+                type="text"
+                placeholder="Profile name"
+                prop:value=name
+                on:input=move |ev| set_name(event_target_value(&ev))
+            />
+            <button on:click=create>"Save Current Settings"</button>
+        </details>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> Profile {
+        Profile {
+            name: "Gridshot".to_owned(),
+            rows: 5,
+            columns: 7,
+            active: 4,
+            mode: Mode::Memory,
+            lives: 3,
+            reaction_trials: 5,
+            min_distance: 2,
+            edge_bias: 30,
+            shrink_every: 10,
+            shrink_min: 3,
+            reveal_delay_min: 100,
+            reveal_delay_max: 250,
+            respawn: Respawn::Cycle,
+            in_order: true,
+            spacing: 2,
+            sprint: 50,
+            live_delay: 150,
+            hit_cooldown: 40,
+        }
+    }
+
+    #[test]
+    fn profiles_round_trip() {
+        let profiles = vec![
+            profile(),
+            Profile {
+                name: "Reflex".to_owned(),
+                mode: Mode::Reaction,
+                ..profile()
+            },
+        ];
+        let json = serde_json::to_string(&profiles).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Profile>>(&json).unwrap(),
+            profiles
+        );
+    }
+
+    #[test]
+    fn older_profiles_load_with_defaults() {
+        let json = r#"{"name":"Old","rows":4,"columns":4,"active":3,"mode":"Classic","lives":1,
+            "reaction_trials":5,"min_distance":0,"edge_bias":0,"shrink_every":0,"shrink_min":2,
+            "reveal_delay_min":0,"reveal_delay_max":0}"#;
+        let profile: Profile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.rows, 4);
+        assert_eq!(profile.respawn, Respawn::One);
+        assert!(!profile.in_order);
+        assert_eq!((profile.spacing, profile.sprint), (0, 0));
+        assert_eq!((profile.live_delay, profile.hit_cooldown), (0, 0));
+    }
+
+    #[test]
+    fn malformed_profiles_are_rejected() {
+        assert!(serde_json::from_str::<Profile>(r#"{"name":"Half","rows":4}"#).is_err());
+    }
+}
@@ -1,13 +1,108 @@
+use std::{cmp::Ordering, fmt};
+
 use serde::*;
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub struct Record(u64, u64, u128, usize, usize);
+use crate::Mode;
+
+const VERSION: u8 = 3;
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Length of [`Record::to_bytes`]: version, position, score, micros, rows, columns, active,
+/// timestamp, mode and distance. Version 1 stored millis in place of micros.
+pub const SERIALIZED_LEN: usize = LEGACY_LEN + 8;
+/// Length of versions 1 and 2, which predate distance.
+const LEGACY_LEN: usize = 1 + 8 + 8 + 8 + 4 + 4 + 4 + 8 + 1;
+
+/// Why [`Record::try_from_str`] or [`Record::try_from_bytes`] couldn't read a record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordParseError {
+    /// The text isn't valid [`encode`]d base64.
+    Base64,
+    /// There were no bytes at all.
+    Empty,
+    /// The leading version byte isn't one this build knows.
+    Version(u8),
+    /// The byte count doesn't match the layout of the record's version.
+    Length { expected: usize, found: usize },
+    /// The mode byte doesn't name a [`Mode`].
+    Mode(u8),
+}
+
+impl fmt::Display for RecordParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Base64 => write!(f, "it isn't valid base64, so it may have been cut short or mistyped"),
+            Self::Empty => write!(f, "it is empty"),
+            Self::Version(version) => write!(
+                f,
+                "it is version {version}, which this version of laim doesn't know, so it may be from a newer one"
+            ),
+            Self::Length { expected, found } => {
+                write!(f, "it is {found} bytes long where {expected} were expected")
+            }
+            Self::Mode(mode) => write!(f, "its mode, {mode}, isn't one laim knows"),
+        }
+    }
+}
+
+impl std::error::Error for RecordParseError {}
+
+/// Which of two runs with equal scores ranks higher.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// The run that took less time, so reaching a score quickly is rewarded.
+    #[default]
+    Faster,
+    /// The run that took more time.
+    Slower,
+}
+
+impl TieBreak {
+    pub const OPTIONS: &'static [(Self, &'static str)] =
+        &[(Self::Faster, "Faster"), (Self::Slower, "Slower")];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record(
+    u64,
+    u64,
+    u128,
+    usize,
+    usize,
+    /// Milliseconds since the Unix epoch at which the run ended, `0` for runs recorded before
+    /// timestamps were tracked.
+    #[serde(default)]
+    u64,
+    /// Mode the run was played in, [`Mode::Classic`] for runs recorded before modes existed.
+    #[serde(default)]
+    Mode,
+    /// Number of active cells during the run, `0` for runs recorded before this was tracked.
+    #[serde(default)]
+    usize,
+    /// Duration of the run in microseconds, `0` for runs only timed to the millisecond.
+    #[serde(default)]
+    u128,
+    /// Total Chebyshev distance between consecutive hits, `0` for runs that didn't track it.
+    #[serde(default)]
+    u64,
+);
 
 #[allow(dead_code)]
 impl Record {
     #[inline]
     pub const fn new(position: u64, score: u64, millis: u128, rows: usize, columns: usize) -> Self {
-        Self(position, score, millis, rows, columns)
+        Self(
+            position,
+            score,
+            millis,
+            rows,
+            columns,
+            0,
+            Mode::Classic,
+            0,
+            0,
+            0,
+        )
     }
 
     #[inline]
@@ -38,6 +133,48 @@ impl Record {
     #[inline]
     pub fn set_millis(&mut self, value: u128) {
         self.2 = value;
+        self.8 = 0;
+    }
+
+    /// Duration of the run in microseconds, derived from [`Record::millis`] for runs that were
+    /// only timed to the millisecond.
+    #[inline]
+    pub const fn micros(&self) -> u128 {
+        if self.8 != 0 {
+            self.8
+        } else {
+            self.2 * 1000
+        }
+    }
+
+    #[inline]
+    pub fn set_micros(&mut self, value: u128) {
+        self.2 = value / 1000;
+        self.8 = value;
+    }
+
+    #[inline]
+    pub fn seconds(&self) -> f64 {
+        self.micros() as f64 / 1_000_000f64
+    }
+
+    /// Hits per second, or `0.0` for runs that took no measurable time, such as single hits or
+    /// legacy records saved without a duration.
+    #[inline]
+    pub fn rate(&self) -> f64 {
+        if self.micros() == 0 {
+            return 0.0;
+        }
+        self.score() as f64 / self.seconds()
+    }
+
+    /// [`Record::rate`] to two decimal places, or `--` when it is undefined.
+    pub fn rate_text(&self) -> String {
+        if self.micros() == 0 {
+            "--".to_owned()
+        } else {
+            format!("{:.2}", self.rate())
+        }
     }
 
     #[inline]
@@ -59,4 +196,385 @@ impl Record {
     pub fn set_columns(&mut self, value: usize) {
         self.4 = value;
     }
+
+    #[inline]
+    pub const fn timestamp(&self) -> u64 {
+        self.5
+    }
+
+    #[inline]
+    pub fn set_timestamp(&mut self, value: u64) {
+        self.5 = value;
+    }
+
+    #[inline]
+    pub const fn mode(&self) -> Mode {
+        self.6
+    }
+
+    #[inline]
+    pub fn set_mode(&mut self, value: Mode) {
+        self.6 = value;
+    }
+
+    #[inline]
+    pub const fn active(&self) -> usize {
+        self.7
+    }
+
+    #[inline]
+    pub fn set_active(&mut self, value: usize) {
+        self.7 = value;
+    }
+
+    #[inline]
+    pub const fn distance(&self) -> u64 {
+        self.9
+    }
+
+    #[inline]
+    pub fn set_distance(&mut self, value: u64) {
+        self.9 = value;
+    }
+
+    /// Counts a hit, holding at the top of the range rather than wrapping.
+    #[inline]
+    pub fn add_hit(&mut self) {
+        self.set_score(self.score().saturating_add(1));
+    }
+
+    /// Adds `cells` to the distance travelled, holding at the top of the range rather than
+    /// wrapping.
+    #[inline]
+    pub fn add_distance(&mut self, cells: u64) {
+        self.set_distance(self.distance().saturating_add(cells));
+    }
+
+    /// Cells travelled per second, or `0.0` for a run that took no time.
+    #[inline]
+    pub fn distance_rate(&self) -> f64 {
+        if self.micros() == 0 {
+            return 0.0;
+        }
+        self.distance() as f64 / self.seconds()
+    }
+
+    /// Orders records by score, breaking ties according to `tie_break`.
+    pub fn cmp_by_score(&self, other: &Self, tie_break: TieBreak) -> Ordering {
+        self.score()
+            .cmp(&other.score())
+            .then_with(|| match tie_break {
+                TieBreak::Faster => other.micros().cmp(&self.micros()),
+                TieBreak::Slower => self.micros().cmp(&other.micros()),
+            })
+    }
+
+    /// Orders records by duration, ranking the quicker run higher, for comparing runs that reached
+    /// the same score.
+    pub fn cmp_by_time(&self, other: &Self) -> Ordering {
+        other.micros().cmp(&self.micros())
+    }
+
+    /// Orders records by hits per second, ranking zero-duration runs (whose rate is undefined)
+    /// below every other run.
+    pub fn cmp_by_rate(&self, other: &Self) -> Ordering {
+        let rate = |record: &Self| (record.micros() != 0).then(|| record.rate());
+        match (rate(self), rate(other)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        }
+    }
+
+    /// Packs the record into a fixed-size little-endian byte layout, saturating fields that
+    /// don't fit.
+    pub fn to_bytes(self) -> [u8; SERIALIZED_LEN] {
+        fn narrow(value: usize) -> u32 {
+            value.try_into().unwrap_or(u32::MAX)
+        }
+
+        let mut bytes = [0; SERIALIZED_LEN];
+        let mut writer = bytes.iter_mut();
+        let mut write = |chunk: &[u8]| {
+            for (src, dst) in chunk.iter().zip(writer.by_ref()) {
+                *dst = *src;
+            }
+        };
+
+        write(&[VERSION]);
+        write(&self.position().to_le_bytes());
+        write(&self.score().to_le_bytes());
+        write(
+            &u64::try_from(self.micros())
+                .unwrap_or(u64::MAX)
+                .to_le_bytes(),
+        );
+        write(&narrow(self.rows()).to_le_bytes());
+        write(&narrow(self.columns()).to_le_bytes());
+        write(&narrow(self.active()).to_le_bytes());
+        write(&self.timestamp().to_le_bytes());
+        write(&[self.mode() as u8]);
+        write(&self.distance().to_le_bytes());
+        bytes
+    }
+
+    /// Inverse of [`Record::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_from_bytes(bytes).ok()
+    }
+
+    /// [`Record::from_bytes`], with the reason for rejecting `bytes`.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, RecordParseError> {
+        let expected = match *bytes.first().ok_or(RecordParseError::Empty)? {
+            1 | 2 => LEGACY_LEN,
+            VERSION => SERIALIZED_LEN,
+            version => return Err(RecordParseError::Version(version)),
+        };
+        if bytes.len() != expected {
+            return Err(RecordParseError::Length {
+                expected,
+                found: bytes.len(),
+            });
+        }
+
+        let mut rest = &bytes[1..];
+        let mut take = |len: usize| {
+            let (chunk, tail) = rest.split_at(len);
+            rest = tail;
+            chunk
+        };
+        let u64 = |chunk: &[u8]| u64::from_le_bytes(chunk.try_into().unwrap());
+        let u32 = |chunk: &[u8]| u32::from_le_bytes(chunk.try_into().unwrap());
+
+        let position = u64(take(8));
+        let score = u64(take(8));
+        let duration = u64(take(8));
+        let rows = u32(take(4));
+        let columns = u32(take(4));
+        let active = u32(take(4));
+        let timestamp = u64(take(8));
+        let mode = take(1)[0];
+        let mode = Mode::from_byte(mode).ok_or(RecordParseError::Mode(mode))?;
+        let distance = if expected == SERIALIZED_LEN {
+            u64(take(8))
+        } else {
+            0
+        };
+
+        let mut record = Self::new(position, score, 0, rows as usize, columns as usize);
+        record.set_timestamp(timestamp);
+        record.set_mode(mode);
+        record.set_active(active as usize);
+        record.set_distance(distance);
+        match bytes[0] {
+            1 => record.set_millis(duration as u128),
+            _ => record.set_micros(duration as u128),
+        }
+        Ok(record)
+    }
+
+    /// Encodes the record as [`encode`]d [`Record::to_bytes`].
+    pub fn to_str(self) -> String {
+        encode(&self.to_bytes())
+    }
+
+    /// Inverse of [`Record::to_str`].
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::try_from_str(s).ok()
+    }
+
+    /// [`Record::from_str`], with the reason for rejecting `s`.
+    pub fn try_from_str(s: &str) -> Result<Self, RecordParseError> {
+        Self::try_from_bytes(&decode(s).ok_or(RecordParseError::Base64)?)
+    }
+}
+
+/// Encodes `bytes` as URL-safe unpadded base64.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (idx, &byte)| {
+            acc | (byte as u32) << (16 - idx * 8)
+        });
+        for idx in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - idx * 6)) as usize & 0x3f] as char);
+        }
+    }
+    out
+}
+
+/// Inverse of [`encode`].
+pub fn decode(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+
+        let mut n = 0u32;
+        for (idx, &c) in chunk.iter().enumerate() {
+            let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+            n |= value << (18 - idx * 6);
+        }
+        for idx in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - idx * 8)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(score: u64, millis: u128) -> Record {
+        Record::new(1, score, millis, 3, 3)
+    }
+
+    #[test]
+    fn higher_rate_ranks_higher() {
+        let quick = run(10, 2000);
+        let slow = run(10, 5000);
+        assert_eq!(quick.cmp_by_rate(&slow), Ordering::Greater);
+        assert_eq!(slow.cmp_by_rate(&quick), Ordering::Less);
+        assert_eq!(quick.cmp_by_rate(&run(5, 1000)), Ordering::Equal);
+    }
+
+    #[test]
+    fn zero_time_ranks_below_any_rate() {
+        let instant = run(1, 0);
+        let slowest = run(1, 60 * 60 * 1000);
+        assert_eq!(instant.rate(), 0.0);
+        assert_eq!(instant.cmp_by_rate(&slowest), Ordering::Less);
+        assert_eq!(slowest.cmp_by_rate(&instant), Ordering::Greater);
+        assert_eq!(instant.cmp_by_rate(&run(5, 0)), Ordering::Equal);
+    }
+
+    #[test]
+    fn zero_time_travels_at_no_rate() {
+        let mut instant = run(1, 0);
+        instant.set_distance(4);
+        assert_eq!(instant.distance_rate(), 0.0);
+
+        let mut timed = run(1, 2000);
+        timed.set_distance(4);
+        assert_eq!(timed.distance_rate(), 2.0);
+    }
+
+    #[test]
+    fn hits_and_distance_saturate() {
+        let mut record = run(u64::MAX - 1, 1000);
+        record.add_hit();
+        record.add_hit();
+        assert_eq!(record.score(), u64::MAX);
+
+        record.set_distance(u64::MAX - 3);
+        record.add_distance(2);
+        assert_eq!(record.distance(), u64::MAX - 1);
+        record.add_distance(8);
+        assert_eq!(record.distance(), u64::MAX);
+    }
+
+    #[test]
+    fn zero_millis_runs_sort_last_in_a_bucket() {
+        for bucket in [
+            [run(4, 0), run(10, 5000), run(3, 1000)],
+            [run(10, 5000), run(3, 1000), run(4, 0)],
+        ] {
+            let mut bucket = bucket.to_vec();
+            bucket.sort_by(|a, b| b.cmp_by_rate(a));
+            let millis: Vec<_> = bucket.iter().map(Record::millis).collect();
+            assert_eq!(millis, [1000, 5000, 0]);
+        }
+    }
+
+    #[test]
+    fn zero_millis_rates_read_as_undefined() {
+        let instant = run(4, 0);
+        assert!(instant.rate().is_finite());
+        assert_eq!(instant.rate_text(), "--");
+        assert_eq!(run(3, 2000).rate_text(), "1.50");
+    }
+
+    #[test]
+    fn faster_tie_break_prefers_the_quicker_run() {
+        let (quick, slow) = (run(10, 5000), run(10, 60_000));
+        assert_eq!(
+            quick.cmp_by_score(&slow, TieBreak::Faster),
+            Ordering::Greater
+        );
+        assert_eq!(slow.cmp_by_score(&quick, TieBreak::Faster), Ordering::Less);
+    }
+
+    #[test]
+    fn slower_tie_break_prefers_the_longer_run() {
+        let (quick, slow) = (run(10, 5000), run(10, 60_000));
+        assert_eq!(
+            slow.cmp_by_score(&quick, TieBreak::Slower),
+            Ordering::Greater
+        );
+        assert_eq!(quick.cmp_by_score(&slow, TieBreak::Slower), Ordering::Less);
+    }
+
+    #[test]
+    fn tie_break_never_outranks_score() {
+        let (better, worse) = (run(11, 60_000), run(10, 5000));
+        for tie_break in [TieBreak::Faster, TieBreak::Slower] {
+            assert_eq!(better.cmp_by_score(&worse, tie_break), Ordering::Greater);
+        }
+    }
+
+    #[test]
+    fn best_of_equal_scores_is_the_faster_run() {
+        // Equal scores once went to the slower run, so a 10 in 60s beat a 10 in 5s.
+        for history in [
+            [run(10, 60_000), run(10, 5000), run(9, 1000)],
+            [run(9, 1000), run(10, 5000), run(10, 60_000)],
+        ] {
+            let best = history
+                .iter()
+                .max_by(|a, b| a.cmp_by_score(b, TieBreak::default()))
+                .unwrap();
+            assert_eq!(best.millis(), 5000);
+        }
+    }
+
+    #[test]
+    fn parse_errors_name_what_is_wrong() {
+        let bytes = run(10, 5000).to_bytes();
+        assert_eq!(
+            Record::try_from_bytes(&bytes).map(Record::to_bytes),
+            Ok(bytes)
+        );
+        assert_eq!(
+            Record::try_from_str(&run(10, 5000).to_str()).map(Record::to_bytes),
+            Ok(bytes)
+        );
+
+        assert_eq!(
+            Record::try_from_str("not base64!"),
+            Err(RecordParseError::Base64)
+        );
+        assert_eq!(Record::try_from_bytes(&[]), Err(RecordParseError::Empty));
+        assert_eq!(
+            Record::try_from_bytes(&[200; SERIALIZED_LEN]),
+            Err(RecordParseError::Version(200))
+        );
+        assert_eq!(
+            Record::try_from_bytes(&bytes[..SERIALIZED_LEN - 1]),
+            Err(RecordParseError::Length {
+                expected: SERIALIZED_LEN,
+                found: SERIALIZED_LEN - 1,
+            })
+        );
+
+        // The mode byte follows the version, the 8-byte position, score and duration, the
+        // 4-byte dimensions and the 8-byte timestamp.
+        let mut bad_mode = bytes;
+        bad_mode[1 + 8 * 3 + 4 * 3 + 8] = 250;
+        assert_eq!(
+            Record::try_from_bytes(&bad_mode),
+            Err(RecordParseError::Mode(250))
+        );
+    }
 }
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{storage, SignalPair};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    Help,
+    Dismiss,
+    Trigger,
+    Restart,
+    Pause,
+    Bank,
+}
+
+impl Action {
+    /// Every action with its default key and a description, used to build the default
+    /// [`Bindings`] and to render the help overlay and settings. A `None` key leaves the action
+    /// unbound, except for [`Action::Trigger`], which then matches whatever no other binding
+    /// claims.
+    pub const DEFAULTS: &'static [(Self, Option<&'static str>, &'static str)] = &[
+        (Self::Help, Some("?"), "Show or hide this help"),
+        (Self::Dismiss, Some("Escape"), "Close this help"),
+        (
+            Self::Trigger,
+            None,
+            "Hit the hovered cell, or miss if no cell is hovered",
+        ),
+        (
+            Self::Restart,
+            None,
+            "End the current run and start a new one",
+        ),
+        (Self::Pause, None, "Pause the run behind the start gate"),
+        (Self::Bank, None, "Save the current run and start a new one"),
+    ];
+}
+
+/// Which key, as reported by `KeyboardEvent.key`, triggers each action.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bindings(BTreeMap<Action, String>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self(
+            Action::DEFAULTS
+                .iter()
+                .filter_map(|&(action, key, _)| Some((action, key?.to_owned())))
+                .collect(),
+        )
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> Option<&str> {
+        self.0.get(&action).map(String::as_str)
+    }
+
+    pub fn set(&mut self, action: Action, key: Option<String>) {
+        match key {
+            Some(key) => self.0.insert(action, key),
+            None => self.0.remove(&action),
+        };
+    }
+
+    /// The action bound to `key`, falling back to [`Action::Trigger`] while it has no key of its
+    /// own.
+    pub fn action(&self, key: &str) -> Option<Action> {
+        self.0
+            .iter()
+            .find(|(_, bound)| *bound == key)
+            .map(|(action, _)| *action)
+            .or_else(|| (!self.0.contains_key(&Action::Trigger)).then_some(Action::Trigger))
+    }
+
+    /// Keys bound to more than one action, of which only the first is reachable.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut keys: Vec<_> = self.0.values().collect();
+        keys.sort();
+        let mut conflicts: Vec<_> = keys
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .map(|pair| pair[0].to_string())
+            .collect();
+        conflicts.dedup();
+        conflicts
+    }
+}
+
+/// Settings for remapping each action to a different key. Focus an action's field and press the
+/// new key, or Backspace to unbind it.
+#[component]
+pub fn KeyBindings(cx: Scope, bindings: SignalPair<Bindings>) -> impl IntoView {
+    let (bindings, set_bindings) = bindings;
+    let save = move || {
+        let _ = storage::set("bindings", bindings());
+    };
+
+    view! { cx,
+        <details class="KeyBindings">
+            <summary>"Key Bindings"</summary>
+            <table>
+                {Action::DEFAULTS
+                    .iter()
+                    .map(|&(action, _, description)| {
+                        let placeholder = if action == Action::Trigger { "Any other key" } else { "Unbound" };
+                        view! { cx,
+                            <tr>
+                                <td>{description}</td>
+                                <td>
+                                    <input
+                                        type="text"
+                                        readonly
+                                        placeholder=placeholder
+                                        prop:value=move || bindings.with(|bindings| bindings.get(action).unwrap_or_default().to_owned())
+                                        on:keydown=move |ev| {
+                                            // Tab and Shift+Tab still move focus, so keyboard
+                                            // users can leave the field without rebinding it.
+                                            let key = ev.key();
+                                            if key == "Tab" {
+                                                return;
+                                            }
+                                            ev.prevent_default();
+                                            let key = (key != "Backspace" && key != "Delete").then_some(key);
+                                            set_bindings.update(|bindings| bindings.set(action, key));
+                                            save();
+                                        }
+                                    />
+                                </td>
+                            </tr>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </table>
+            {move || {
+                let conflicts = bindings.with(Bindings::conflicts);
+                (!conflicts.is_empty()).then(|| view! { cx,
+                    <p class="KeyBindings error">
+                        {format!("Bound to more than one action: {}", conflicts.join(", "))}
+                    </p>
+                })
+            }}
+            <button on:click=move |_| {
+                set_bindings(Bindings::default());
+                save();
+            }>"Reset Bindings"</button>
+        </details>
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{max_active, Position};
+
+/// The targets on a board along with its size, for setting up the exact same board again.
+pub struct Snapshot {
+    pub rows: usize,
+    pub columns: usize,
+    pub positions: Vec<Position>,
+}
+
+impl Snapshot {
+    /// Encodes the snapshot as `ROWSxCOLUMNS:` followed by each target's cell index, counting
+    /// along rows from the top left, separated by dots.
+    pub fn to_str(&self) -> String {
+        let mut cells: Vec<_> = self
+            .positions
+            .iter()
+            .map(|&(row, col)| row * self.columns + col)
+            .collect();
+        cells.sort_unstable();
+        let cells: Vec<_> = cells.iter().map(usize::to_string).collect();
+        format!("{}x{}:{}", self.rows, self.columns, cells.join("."))
+    }
+
+    /// Inverse of [`Snapshot::to_str`], rejecting boards with targets outside the grid, repeated
+    /// targets, or no free cell left.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        let malformed = || "Expected a board like 4x4:0.5.10.".to_owned();
+        let (size, cells) = s.trim().split_once(':').ok_or_else(malformed)?;
+        let (rows, columns) = size.split_once('x').ok_or_else(malformed)?;
+        let rows: usize = rows.parse().map_err(|_| malformed())?;
+        let columns: usize = columns.parse().map_err(|_| malformed())?;
+        if rows.saturating_mul(columns) < 2 {
+            return Err(format!(
+                "A {rows}×{columns} grid has no room for targets to move."
+            ));
+        }
+
+        let mut positions = Vec::new();
+        for cell in cells.split('.') {
+            let cell: usize = cell.parse().map_err(|_| malformed())?;
+            if cell / columns >= rows {
+                return Err(format!("Cell {cell} is outside a {rows}×{columns} grid."));
+            }
+            let position = (cell / columns, cell % columns);
+            if positions.contains(&position) {
+                return Err(format!("Cell {cell} is listed more than once."));
+            }
+            positions.push(position);
+        }
+
+        if positions.len() > max_active(rows, columns) {
+            return Err(format!(
+                "{} targets don't fit on a {rows}×{columns} grid, as one cell is always left free.",
+                positions.len()
+            ));
+        }
+
+        Ok(Self {
+            rows,
+            columns,
+            positions,
+        })
+    }
+}
@@ -0,0 +1,361 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Position, Positions};
+
+const ATTEMPTS: usize = 256;
+
+/// Which targets move when one is hit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Respawn {
+    /// Only the hit target moves, the others staying where they are.
+    #[default]
+    One,
+    /// Every target moves to a fresh position.
+    All,
+    /// Targets follow a shuffled order of every cell, visiting each once before the order
+    /// repeats. The order is reshuffled for every run.
+    Cycle,
+}
+
+impl Respawn {
+    pub const OPTIONS: &'static [(Self, &'static str)] = &[
+        (Self::One, "Replace Hit"),
+        (Self::All, "Refresh All"),
+        (Self::Cycle, "Shuffled Cycle"),
+    ];
+}
+
+/// Decides where targets appear on a `rows` × `columns` board.
+#[derive(Clone, Copy)]
+pub struct Spawner {
+    pub rows: usize,
+    pub columns: usize,
+    /// Minimum Chebyshev distance between a hit cell and its replacement.
+    pub min_distance: usize,
+    /// How much more likely a corner is to be picked than the centre, minus one; `0.0` is uniform.
+    pub edge_bias: f64,
+    /// Minimum Chebyshev distance between targets, kept to where the free cells allow it.
+    pub spacing: usize,
+}
+
+impl Spawner {
+    /// Tops `current` up with distinct random positions until it holds `active` of them, each at
+    /// least `spacing` away from the others unless no free cell is.
+    ///
+    /// `active` must be less than `rows * columns`.
+    pub fn fill<R: Rng>(&self, current: &mut Positions, active: usize, rng: &mut R) {
+        while current.len() < active {
+            let spaced = (0..ATTEMPTS)
+                .map(|_| self.random(rng))
+                .find(|&new| !current.contains(&new) && self.spaced(current, new));
+            current.insert(spaced.unwrap_or_else(|| self.random(rng)));
+        }
+    }
+
+    /// Picks a position not in `current` that is at least `min_distance` away from `from`, falling
+    /// back to any free position if the grid is too small or crowded to allow it.
+    pub fn replacement<R: Rng>(
+        &self,
+        current: &Positions,
+        from: Position,
+        rng: &mut R,
+    ) -> Position {
+        if self.reachable(from) {
+            for _ in 0..ATTEMPTS {
+                let new = self.random(rng);
+                if !current.contains(&new)
+                    && distance(from, new) >= self.min_distance
+                    && self.spaced(current, new)
+                {
+                    return new;
+                }
+            }
+        }
+
+        let mut new = self.random(rng);
+        while current.contains(&new) {
+            new = self.random(rng);
+        }
+        new
+    }
+
+    /// Samples a position, weighting each cell by `1 + edge_bias * d` where `d` is its normalised
+    /// distance from the centre (`0.0` in the middle, `1.0` in the corners).
+    pub fn random<R: Rng>(&self, rng: &mut R) -> Position {
+        loop {
+            let new = (rng.gen_range(0..self.rows), rng.gen_range(0..self.columns));
+            if self.edge_bias <= 0.0 {
+                return new;
+            }
+
+            let weight = 1.0 + self.edge_bias * self.spread(new);
+            if rng.gen::<f64>() * (1.0 + self.edge_bias) < weight {
+                return new;
+            }
+        }
+    }
+
+    /// Normalised distance of `position` from the centre of the grid.
+    fn spread(&self, position: Position) -> f64 {
+        fn axis(idx: usize, len: usize) -> f64 {
+            let half = (len - 1) as f64 / 2.0;
+            if half == 0.0 {
+                0.0
+            } else {
+                (idx as f64 - half).abs() / half
+            }
+        }
+
+        let rows = axis(position.0, self.rows);
+        let columns = axis(position.1, self.columns);
+        ((rows * rows + columns * columns) / 2.0).sqrt()
+    }
+
+    /// Whether `new` is at least `spacing` away from every position in `current`.
+    fn spaced(&self, current: &Positions, new: Position) -> bool {
+        self.spacing <= 1
+            || current
+                .iter()
+                .all(|&other| distance(other, new) >= self.spacing)
+    }
+
+    /// Whether any cell of the grid lies at least `min_distance` away from `from`.
+    #[inline]
+    const fn reachable(&self, from: Position) -> bool {
+        from.0 >= self.min_distance
+            || self.rows - 1 - from.0 >= self.min_distance
+            || from.1 >= self.min_distance
+            || self.columns - 1 - from.1 >= self.min_distance
+    }
+}
+
+#[inline]
+pub const fn distance(a: Position, b: Position) -> usize {
+    let rows = a.0.abs_diff(b.0);
+    let columns = a.1.abs_diff(b.1);
+    if rows > columns {
+        rows
+    } else {
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn spawner(rows: usize, columns: usize) -> Spawner {
+        Spawner {
+            rows,
+            columns,
+            min_distance: 0,
+            edge_bias: 0.0,
+            spacing: 0,
+        }
+    }
+
+    /// Average picks per corner cell over picks of the centre cell, from `samples` draws on a
+    /// 9×9 grid.
+    fn corner_to_centre(edge_bias: f64, samples: usize) -> f64 {
+        let spawner = Spawner {
+            edge_bias,
+            ..spawner(9, 9)
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut corners, mut centre) = (0, 0);
+        for _ in 0..samples {
+            match spawner.random(&mut rng) {
+                (0 | 8, 0 | 8) => corners += 1,
+                (4, 4) => centre += 1,
+                _ => {}
+            }
+        }
+        corners as f64 / 4.0 / centre as f64
+    }
+
+    #[test]
+    fn edge_bias_favours_corners_by_its_weight() {
+        // A corner weighs `1 + edge_bias` against the centre's `1`.
+        let ratio = corner_to_centre(3.0, 200_000);
+        assert!(
+            (3.4..4.6).contains(&ratio),
+            "corners picked {ratio} times as often"
+        );
+    }
+
+    #[test]
+    fn no_edge_bias_is_uniform() {
+        let ratio = corner_to_centre(0.0, 200_000);
+        assert!(
+            (0.85..1.15).contains(&ratio),
+            "corners picked {ratio} times as often"
+        );
+    }
+
+    #[test]
+    fn replacements_keep_min_distance() {
+        let spawner = Spawner {
+            min_distance: 5,
+            ..spawner(20, 20)
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut current = Positions::default();
+        spawner.fill(&mut current, 3, &mut rng);
+
+        for _ in 0..1000 {
+            let hit = *current.iter().next().unwrap();
+            current.remove(&hit);
+            let new = spawner.replacement(&current, hit, &mut rng);
+            assert!(distance(hit, new) >= 5, "{new:?} is too close to {hit:?}");
+            assert!(current.insert(new), "{new:?} is already a target");
+        }
+    }
+
+    #[test]
+    fn replacements_fall_back_when_min_distance_is_unreachable() {
+        let spawner = Spawner {
+            min_distance: 10,
+            ..spawner(3, 3)
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let current: Positions = [(0, 0), (1, 1)].into_iter().collect();
+
+        for _ in 0..100 {
+            let new = spawner.replacement(&current, (1, 1), &mut rng);
+            assert!(!current.contains(&new));
+            assert!(new.0 < 3 && new.1 < 3);
+        }
+    }
+
+    #[test]
+    fn single_line_grids_fill_every_cell_but_one() {
+        for (rows, columns) in [(1, 8), (8, 1)] {
+            let spawner = Spawner {
+                edge_bias: 2.0,
+                spacing: 2,
+                ..spawner(rows, columns)
+            };
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut current = Positions::default();
+            spawner.fill(&mut current, 7, &mut rng);
+
+            assert_eq!(current.len(), 7);
+            assert!(current
+                .iter()
+                .all(|&(row, col)| row < rows && col < columns));
+        }
+    }
+
+    #[test]
+    fn single_line_replacements_stay_on_the_line() {
+        for (rows, columns) in [(1, 10), (10, 1)] {
+            let spawner = Spawner {
+                min_distance: 4,
+                ..spawner(rows, columns)
+            };
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut current = Positions::default();
+            spawner.fill(&mut current, 2, &mut rng);
+
+            for _ in 0..200 {
+                let hit = *current.iter().next().unwrap();
+                current.remove(&hit);
+                let new = spawner.replacement(&current, hit, &mut rng);
+                assert!(new.0 < rows && new.1 < columns, "{new:?} is off the grid");
+                assert!(distance(hit, new) >= 4, "{new:?} is too close to {hit:?}");
+                assert!(current.insert(new), "{new:?} is already a target");
+            }
+        }
+    }
+
+    #[test]
+    fn two_cell_line_always_swaps() {
+        let spawner = Spawner {
+            min_distance: 3,
+            ..spawner(1, 2)
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        // The hit cell is still occupied while its replacement is picked.
+        let current: Positions = [(0, 0)].into_iter().collect();
+        for _ in 0..100 {
+            assert_eq!(spawner.replacement(&current, (0, 0), &mut rng), (0, 1));
+        }
+    }
+
+    /// Smallest distance between any two of `positions`.
+    fn closest(positions: &Positions) -> usize {
+        positions
+            .iter()
+            .flat_map(|&a| {
+                positions
+                    .iter()
+                    .filter(move |&&b| b != a)
+                    .map(move |&b| distance(a, b))
+            })
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+
+    #[test]
+    fn spacing_is_kept_when_feasible() {
+        // Four targets three apart leave plenty of room on a 9×9 grid.
+        let spawner = Spawner {
+            spacing: 3,
+            ..spawner(9, 9)
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let mut current = Positions::default();
+            spawner.fill(&mut current, 4, &mut rng);
+            assert!(closest(&current) >= 3, "{current:?} is packed too tightly");
+
+            for _ in 0..20 {
+                let hit = *current.iter().next().unwrap();
+                let new = spawner.replacement(&current, hit, &mut rng);
+                current.remove(&hit);
+                current.insert(new);
+                assert!(closest(&current) >= 3, "{current:?} is packed too tightly");
+            }
+        }
+    }
+
+    #[test]
+    fn spacing_spreads_targets_compared_to_uniform() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut mean_closest = |spacing| {
+            let spawner = Spawner {
+                spacing,
+                ..spawner(20, 20)
+            };
+            let total: usize = (0..500)
+                .map(|_| {
+                    let mut current = Positions::default();
+                    spawner.fill(&mut current, 8, &mut rng);
+                    closest(&current)
+                })
+                .sum();
+            total as f64 / 500.0
+        };
+        let (uniform, spaced) = (mean_closest(0), mean_closest(4));
+        assert!(spaced >= 4.0, "spaced targets averaged {spaced} apart");
+        assert!(
+            spaced > uniform,
+            "{spaced} is no wider than uniform's {uniform}"
+        );
+    }
+
+    #[test]
+    fn infeasible_spacing_falls_back_to_filling() {
+        let spawner = Spawner {
+            spacing: 10,
+            ..spawner(4, 4)
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut current = Positions::default();
+        spawner.fill(&mut current, 15, &mut rng);
+        assert_eq!(current.len(), 15);
+    }
+}
@@ -0,0 +1,28 @@
+use leptos::window;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Reads `text` aloud with the Web Speech API, cutting off anything still being read so
+/// announcements never pile up. Does nothing where speech synthesis isn't available. The API is
+/// looked up by name so browsers without it don't fail to load.
+pub fn speak(text: &str) -> Result<(), JsValue> {
+    let global = JsValue::from(window());
+    let synthesis = js_sys::Reflect::get(&global, &JsValue::from_str("speechSynthesis"))?;
+    let utterance = js_sys::Reflect::get(&global, &JsValue::from_str("SpeechSynthesisUtterance"))?;
+    let (Some(synthesis), Some(utterance)) = (
+        (!synthesis.is_undefined()).then_some(synthesis),
+        utterance.dyn_into::<js_sys::Function>().ok(),
+    ) else {
+        return Ok(());
+    };
+
+    let utterance =
+        js_sys::Reflect::construct(&utterance, &js_sys::Array::of1(&JsValue::from_str(text)))?;
+    let call = |method: &str, args: &js_sys::Array| -> Result<(), JsValue> {
+        let method: js_sys::Function =
+            js_sys::Reflect::get(&synthesis, &JsValue::from_str(method))?.dyn_into()?;
+        method.apply(&synthesis, args)?;
+        Ok(())
+    };
+    call("cancel", &js_sys::Array::new())?;
+    call("speak", &js_sys::Array::of1(&utterance))
+}
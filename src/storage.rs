@@ -0,0 +1,636 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+};
+
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use leptos::*;
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::{record::SERIALIZED_LEN, Record};
+
+/// Prefix of every key laim stores unless [`set_namespace`] picks another, so it can share an
+/// origin with other apps.
+pub const DEFAULT_NAMESPACE: &str = "laim:";
+
+const HISTORY: &str = "history";
+const HISTORY_LOG: &str = "history_log";
+
+/// Number of appended records after which the log is folded back into the snapshot.
+const COMPACT_AFTER: usize = 64;
+/// Characters most browsers allow an origin in localStorage. The real quota varies, so this is
+/// only a guide.
+pub const QUOTA_ESTIMATE: usize = 5 * 1024 * 1024;
+
+/// Where the run history is persisted.
+///
+/// [`App`](crate::App) looks the store up through context and falls back to
+/// [`LocalStorageStore`], so a different backend, such as one that also posts the history to a
+/// server for cross-device sync, can be supplied by providing it above the app:
+///
+/// ```ignore
+/// mount_to_body(|cx| {
+///     provide_context::<Store>(cx, Rc::new(ServerStore::new("https://example.com/history")));
+///     view! { cx, <App/> }
+/// })
+/// ```
+pub trait HistoryStore {
+    /// Returns the history as a JSON array, or an empty string if nothing has been saved.
+    fn load(&self) -> String;
+
+    /// Replaces the stored history with `history`, a JSON array.
+    fn save(&self, history: &str);
+
+    /// Appends a single JSON-encoded record without rewriting the rest of the history, returning
+    /// whether it did so. Stores that can't append cheaply keep the default, which makes the
+    /// caller fall back to [`save`](Self::save).
+    fn append(&self, _record: &str) -> bool {
+        false
+    }
+
+    fn clear(&self) {
+        self.save("");
+    }
+}
+
+pub type Store = Rc<dyn HistoryStore>;
+
+thread_local! {
+    static NAMESPACE: RefCell<String> = RefCell::new(DEFAULT_NAMESPACE.to_owned());
+    /// Filled from localStorage the first time [`stored_size`] is asked for, then kept up to date
+    /// as values are written, so the readout never re-reads or re-serializes them.
+    static USAGE: RefCell<Option<Usage>> = const { RefCell::new(None) };
+}
+
+/// Prefixes every key laim stores with `namespace` instead of [`DEFAULT_NAMESPACE`], for pages
+/// that embed more than one copy of laim or already use `"laim:"` for something else. Call it
+/// before mounting [`App`](crate::App).
+pub fn set_namespace(namespace: impl Into<String>) {
+    NAMESPACE.with(|current| *current.borrow_mut() = namespace.into());
+}
+
+/// The default store: a snapshot under `"history"` plus an append-only log of newer records
+/// under `"history_log"`.
+pub struct LocalStorageStore;
+
+impl HistoryStore for LocalStorageStore {
+    fn load(&self) -> String {
+        let backend = LocalStorage::raw();
+        migrate(&backend, HISTORY, is_history);
+        migrate(&backend, HISTORY_LOG, is_history_log);
+        load_log(&backend)
+    }
+
+    fn save(&self, history: &str) {
+        save_log(&LocalStorage::raw(), history);
+    }
+
+    fn append(&self, record: &str) -> bool {
+        let backend = LocalStorage::raw();
+        migrate(&backend, HISTORY_LOG, is_history_log);
+        append_log(&backend, record)
+    }
+
+    fn clear(&self) {
+        delete(HISTORY);
+        delete(HISTORY_LOG);
+    }
+}
+
+/// The string calls the snapshot and log make on localStorage, so they can run against a map in
+/// tests.
+trait Backend {
+    fn get_item(&self, key: &str) -> Option<String>;
+
+    /// Returns whether the value was stored.
+    fn set_item(&self, key: &str, value: &str) -> bool;
+
+    fn remove_item(&self, key: &str);
+}
+
+impl Backend for web_sys::Storage {
+    fn get_item(&self, key: &str) -> Option<String> {
+        web_sys::Storage::get_item(self, key).ok().flatten()
+    }
+
+    fn set_item(&self, key: &str, value: &str) -> bool {
+        let stored = web_sys::Storage::set_item(self, key, value).is_ok();
+        if stored {
+            track_change(key, Some(value));
+        }
+        stored
+    }
+
+    fn remove_item(&self, key: &str) {
+        let _ = web_sys::Storage::remove_item(self, key);
+        track_change(key, None);
+    }
+}
+
+/// The snapshot with the log replayed over it, as a JSON array.
+fn load_log(backend: &impl Backend) -> String {
+    let snapshot = backend
+        .get_item(&key(HISTORY))
+        .and_then(|snapshot| serde_json::from_str(&snapshot).ok())
+        .unwrap_or_default();
+    let log = backend.get_item(&key(HISTORY_LOG)).unwrap_or_default();
+    serde_json::to_string(&replay(snapshot, &log)).unwrap_or_default()
+}
+
+/// Replaces the snapshot, which folds the log into it.
+fn save_log(backend: &impl Backend, history: &str) {
+    if backend.set_item(&key(HISTORY), history) {
+        backend.remove_item(&key(HISTORY_LOG));
+    }
+}
+
+/// Appends `record` to the log, unless the log is due to be compacted into the snapshot.
+fn append_log(backend: &impl Backend, record: &str) -> bool {
+    let mut log = backend.get_item(&key(HISTORY_LOG)).unwrap_or_default();
+
+    if log.lines().count() + 1 >= COMPACT_AFTER {
+        return false;
+    }
+
+    log.push_str(record);
+    log.push('\n');
+    backend.set_item(&key(HISTORY_LOG), &log)
+}
+
+/// The namespaced localStorage key for `name`.
+pub fn key(name: &str) -> String {
+    NAMESPACE.with(|namespace| format!("{}{name}", namespace.borrow()))
+}
+
+/// Reads the JSON value saved under `name`.
+pub fn get<T: DeserializeOwned>(name: &str) -> gloo_storage::Result<T> {
+    migrate(&LocalStorage::raw(), name, |value| {
+        serde_json::from_str::<T>(value).is_ok()
+    });
+    LocalStorage::get(key(name))
+}
+
+/// Saves `value` as JSON under `name`.
+pub fn set<T: Serialize>(name: &str, value: T) -> gloo_storage::Result<()> {
+    let json = serde_json::to_string(&value)?;
+    let key = key(name);
+    LocalStorage::raw()
+        .set_item(&key, &json)
+        .map_err(|err| StorageError::JsError(err.unchecked_into::<js_sys::Error>().into()))?;
+    track_change(&key, Some(&json));
+    Ok(())
+}
+
+pub fn delete(name: &str) {
+    let key = key(name);
+    LocalStorage::delete(&key);
+    track_change(&key, None);
+}
+
+/// The length of each of laim's localStorage entries, key included, in the UTF-16 code units
+/// browsers count against the quota.
+#[derive(Default)]
+struct Usage(HashMap<String, usize>);
+
+impl Usage {
+    /// Notes that `key` now holds `value`, or nothing. Keys outside the namespace are left out.
+    fn record(&mut self, key: &str, value: Option<&str>) {
+        if !key.starts_with(&self::key("")) {
+            return;
+        }
+        match value {
+            Some(value) => {
+                let len = key.encode_utf16().count() + value.encode_utf16().count();
+                self.0.insert(key.to_owned(), len);
+            }
+            None => {
+                self.0.remove(key);
+            }
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+}
+
+/// Notes a write to the localStorage `key`, by this tab or, through a `storage` event, another.
+pub fn track_change(key: &str, value: Option<&str>) {
+    USAGE.with(|usage| {
+        if let Some(usage) = usage.borrow_mut().as_mut() {
+            usage.record(key, value);
+        }
+    });
+}
+
+/// Characters all of laim's keys take up in localStorage, to compare against
+/// [`QUOTA_ESTIMATE`]. Everything under the namespace counts, as the quota is shared.
+pub fn stored_size() -> usize {
+    USAGE.with(|usage| {
+        usage
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let storage = LocalStorage::raw();
+                let mut usage = Usage::default();
+                for idx in 0..storage.length().unwrap_or(0) {
+                    if let Ok(Some(key)) = storage.key(idx) {
+                        usage.record(&key, storage.get_item(&key).ok().flatten().as_deref());
+                    }
+                }
+                usage
+            })
+            .total()
+    })
+}
+
+/// Moves a value saved under the bare `name`, as versions before namespacing did, to its
+/// namespaced key, once nothing is stored under the namespaced key yet.
+///
+/// The bare key is only moved if `recognize` accepts its value as something laim would have
+/// written there, which keeps clear of most other apps' keys. It can't tell laim's `"rows"` from
+/// another app's that also holds a plain number, so such a key is still taken.
+fn migrate(backend: &impl Backend, name: &str, recognize: impl Fn(&str) -> bool) {
+    let key = key(name);
+    if backend.get_item(&key).is_some() {
+        return;
+    }
+
+    if let Some(value) = backend.get_item(name).filter(|value| recognize(value)) {
+        if backend.set_item(&key, &value) {
+            backend.remove_item(name);
+        }
+    }
+}
+
+/// Whether `value` is a history snapshot.
+fn is_history(value: &str) -> bool {
+    serde_json::from_str::<VecDeque<Record>>(value).is_ok()
+}
+
+/// Whether `value` is a history log, one record per line.
+fn is_history_log(value: &str) -> bool {
+    value
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| serde_json::from_str::<Record>(line).is_ok())
+}
+
+/// The store provided through context, or [`LocalStorageStore`] if there is none.
+fn store(cx: Scope) -> Store {
+    use_context(cx).unwrap_or_else(|| Rc::new(LocalStorageStore))
+}
+
+pub fn load_history(cx: Scope) -> VecDeque<Record> {
+    read(&*store(cx))
+}
+
+/// Persists `record`, which must already be at the front of `history`, by appending it where the
+/// store allows rather than rewriting the whole history.
+pub fn append_history(cx: Scope, history: &VecDeque<Record>, record: &Record) {
+    append(&*store(cx), history, record);
+}
+
+/// Rewrites the whole history.
+pub fn save_history(cx: Scope, history: &VecDeque<Record>) {
+    write(&*store(cx), history);
+}
+
+fn read(store: &dyn HistoryStore) -> VecDeque<Record> {
+    serde_json::from_str(&store.load()).unwrap_or_default()
+}
+
+fn append(store: &dyn HistoryStore, history: &VecDeque<Record>, record: &Record) {
+    let Ok(line) = serde_json::to_string(record) else {
+        return;
+    };
+    if !store.append(&line) {
+        write(store, history);
+    }
+}
+
+fn write(store: &dyn HistoryStore, history: &VecDeque<Record>) {
+    if let Ok(history) = serde_json::to_string(history) {
+        store.save(&history);
+    }
+}
+
+pub fn clear_history(cx: Scope) {
+    store(cx).clear();
+}
+
+/// Whether a change to the localStorage `key`, as reported by another tab's `storage` event,
+/// affects the saved history.
+pub fn touches_history(key: &str) -> bool {
+    key == self::key(HISTORY) || key == self::key(HISTORY_LOG)
+}
+
+/// Folds `theirs`, a history saved by another tab, into `ours`. Runs present in both are kept
+/// once, the result is ordered newest first, and positions are renumbered since each tab numbers
+/// its own runs independently.
+pub fn merge(ours: &mut VecDeque<Record>, theirs: VecDeque<Record>) {
+    let identity = |record: &Record| -> [u8; SERIALIZED_LEN] {
+        let mut record = *record;
+        record.set_position(0);
+        record.to_bytes()
+    };
+
+    let mut seen: HashSet<_> = ours.iter().map(identity).collect();
+    let mut added = false;
+    for record in theirs {
+        if seen.insert(identity(&record)) {
+            ours.push_back(record);
+            added = true;
+        }
+    }
+    if !added {
+        return;
+    }
+
+    ours.make_contiguous()
+        .sort_by_key(|record| std::cmp::Reverse(record.timestamp()));
+    renumber(ours);
+}
+
+/// Numbers runs from `1` for the oldest, at the back, up to the newest at the front.
+pub fn renumber(history: &mut VecDeque<Record>) {
+    let len = history.len() as u64;
+    for (idx, record) in history.iter_mut().enumerate() {
+        record.set_position(len - idx as u64);
+    }
+}
+
+/// Pushes every record in `log` onto the front of `snapshot` in order, skipping lines that fail
+/// to parse so a single corrupt write can't take the rest of the history with it.
+fn replay(mut snapshot: VecDeque<Record>, log: &str) -> VecDeque<Record> {
+    for line in log.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(record) => snapshot.push_front(record),
+            Err(err) => tracing::warn!("skipping corrupt history log entry: {err}"),
+        }
+    }
+    snapshot
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap};
+
+    use super::*;
+
+    /// localStorage as a plain map.
+    #[derive(Default)]
+    struct Memory(RefCell<HashMap<String, String>>);
+
+    impl Backend for Memory {
+        fn get_item(&self, key: &str) -> Option<String> {
+            self.0.borrow().get(key).cloned()
+        }
+
+        fn set_item(&self, key: &str, value: &str) -> bool {
+            self.0.borrow_mut().insert(key.to_owned(), value.to_owned());
+            true
+        }
+
+        fn remove_item(&self, key: &str) {
+            self.0.borrow_mut().remove(key);
+        }
+    }
+
+    /// A store that keeps the history in memory, appending only if built with `appends`.
+    #[derive(Default)]
+    struct MockStore {
+        appends: bool,
+        saved: RefCell<String>,
+        appended: RefCell<Vec<String>>,
+    }
+
+    impl HistoryStore for MockStore {
+        fn load(&self) -> String {
+            let snapshot = serde_json::from_str(&self.saved.borrow()).unwrap_or_default();
+            let log = self.appended.borrow().join("\n");
+            serde_json::to_string(&replay(snapshot, &log)).unwrap()
+        }
+
+        fn save(&self, history: &str) {
+            *self.saved.borrow_mut() = history.to_owned();
+            self.appended.borrow_mut().clear();
+        }
+
+        fn append(&self, record: &str) -> bool {
+            if self.appends {
+                self.appended.borrow_mut().push(record.to_owned());
+            }
+            self.appends
+        }
+    }
+
+    fn run(position: u64) -> Record {
+        Record::new(position, position, 1000, 3, 3)
+    }
+
+    fn json<T: Serialize>(value: &T) -> String {
+        serde_json::to_string(value).unwrap()
+    }
+
+    fn loaded(backend: &Memory) -> Vec<u64> {
+        let history: VecDeque<Record> = serde_json::from_str(&load_log(backend)).unwrap();
+        history.iter().map(Record::position).collect()
+    }
+
+    #[test]
+    fn appended_runs_load_newest_first() {
+        let backend = Memory::default();
+        save_log(&backend, &json(&[run(2), run(1)]));
+        assert!(append_log(&backend, &json(&run(3))));
+        assert!(append_log(&backend, &json(&run(4))));
+        assert_eq!(loaded(&backend), [4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn full_log_asks_for_compaction() {
+        let backend = Memory::default();
+        for position in 1..COMPACT_AFTER as u64 {
+            assert!(append_log(&backend, &json(&run(position))));
+        }
+        assert!(!append_log(&backend, &json(&run(COMPACT_AFTER as u64))));
+        assert_eq!(loaded(&backend).len(), COMPACT_AFTER - 1);
+    }
+
+    #[test]
+    fn saving_compacts_the_log_into_the_snapshot() {
+        let backend = Memory::default();
+        assert!(append_log(&backend, &json(&run(1))));
+        assert!(append_log(&backend, &json(&run(2))));
+
+        let history = load_log(&backend);
+        save_log(&backend, &history);
+        assert_eq!(backend.get_item(&key(HISTORY_LOG)), None);
+        assert_eq!(loaded(&backend), [2, 1]);
+
+        assert!(append_log(&backend, &json(&run(3))));
+        assert_eq!(loaded(&backend), [3, 2, 1]);
+    }
+
+    #[test]
+    fn replay_skips_corrupt_lines() {
+        let log = format!(
+            "{}\n{{\"truncated\n\n{}\nnot json\n{}",
+            json(&run(2)),
+            json(&run(3)),
+            json(&run(4))
+        );
+        let history = replay([run(1)].into_iter().collect(), &log);
+        let positions: Vec<_> = history.iter().map(Record::position).collect();
+        assert_eq!(positions, [4, 3, 2, 1]);
+    }
+
+    /// Saves a run the way `Game` does, at the front of `history`.
+    fn finish(store: &MockStore, history: &mut VecDeque<Record>, record: Record) {
+        history.push_front(record);
+        append(store, history, &record);
+    }
+
+    #[test]
+    fn runs_are_appended_where_the_store_allows() {
+        let store = MockStore {
+            appends: true,
+            ..Default::default()
+        };
+        let mut history = VecDeque::new();
+        finish(&store, &mut history, run(1));
+        finish(&store, &mut history, run(2));
+
+        assert!(store.saved.borrow().is_empty());
+        assert_eq!(store.appended.borrow().len(), 2);
+        assert_eq!(read(&store), history);
+    }
+
+    #[test]
+    fn stores_that_cannot_append_are_rewritten() {
+        let store = MockStore::default();
+        let mut history = VecDeque::new();
+        finish(&store, &mut history, run(1));
+        finish(&store, &mut history, run(2));
+
+        assert!(store.appended.borrow().is_empty());
+        assert_eq!(*store.saved.borrow(), json(&history));
+        assert_eq!(read(&store), history);
+    }
+
+    #[test]
+    fn empty_store_reads_as_no_history() {
+        assert!(read(&MockStore::default()).is_empty());
+    }
+
+    /// A history as a tab would save it, newest first and numbered from the oldest.
+    fn tab(timestamps: &[u64]) -> VecDeque<Record> {
+        let mut history: VecDeque<_> = timestamps
+            .iter()
+            .rev()
+            .map(|&timestamp| {
+                let mut record = run(0);
+                record.set_timestamp(timestamp);
+                record.set_score(timestamp);
+                record
+            })
+            .collect();
+        renumber(&mut history);
+        history
+    }
+
+    #[test]
+    fn merge_keeps_runs_from_both_tabs_once() {
+        let mut ours = tab(&[1, 2, 3]);
+        merge(&mut ours, tab(&[1, 2, 5, 4]));
+
+        let timestamps: Vec<_> = ours.iter().map(Record::timestamp).collect();
+        let positions: Vec<_> = ours.iter().map(Record::position).collect();
+        assert_eq!(timestamps, [5, 4, 3, 2, 1]);
+        assert_eq!(positions, [5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn merge_of_known_runs_changes_nothing() {
+        let mut ours = tab(&[1, 2, 3]);
+        let before = ours.clone();
+        merge(&mut ours, tab(&[2, 3]));
+        assert_eq!(ours, before);
+    }
+
+    #[test]
+    fn migration_moves_bare_keys_into_the_namespace() {
+        let backend = Memory::default();
+        let history = json(&[run(2), run(1)]);
+        backend.set_item(HISTORY, &history);
+        backend.set_item(HISTORY_LOG, &format!("{}\n", json(&run(3))));
+
+        migrate(&backend, HISTORY, is_history);
+        migrate(&backend, HISTORY_LOG, is_history_log);
+
+        assert_eq!(backend.get_item(HISTORY), None);
+        assert_eq!(backend.get_item(HISTORY_LOG), None);
+        assert_eq!(backend.get_item(&key(HISTORY)), Some(history));
+        assert_eq!(loaded(&backend), [3, 2, 1]);
+    }
+
+    #[test]
+    fn migration_leaves_unrecognized_keys_alone() {
+        let backend = Memory::default();
+        backend.set_item(HISTORY, "[\"someone else's\"]");
+
+        migrate(&backend, HISTORY, is_history);
+
+        assert_eq!(
+            backend.get_item(HISTORY).as_deref(),
+            Some("[\"someone else's\"]")
+        );
+        assert_eq!(backend.get_item(&key(HISTORY)), None);
+    }
+
+    #[test]
+    fn migration_never_overwrites_the_namespaced_key() {
+        let backend = Memory::default();
+        backend.set_item(HISTORY, &json(&[run(1)]));
+        backend.set_item(&key(HISTORY), &json(&[run(2)]));
+
+        migrate(&backend, HISTORY, is_history);
+
+        assert_eq!(loaded(&backend), [2]);
+        assert!(backend.get_item(HISTORY).is_some());
+    }
+
+    #[test]
+    fn keys_use_the_configured_namespace() {
+        assert_eq!(key(HISTORY), "laim:history");
+        set_namespace("other:");
+        assert_eq!(key(HISTORY), "other:history");
+        set_namespace(DEFAULT_NAMESPACE);
+    }
+
+    #[test]
+    fn usage_follows_writes_to_namespaced_keys() {
+        let mut usage = Usage::default();
+        usage.record("laim:rows", Some("12"));
+        usage.record("laim:history", Some("[]"));
+        assert_eq!(usage.total(), 9 + 2 + 12 + 2);
+
+        usage.record("laim:history", Some("[1,2]"));
+        assert_eq!(usage.total(), 9 + 2 + 12 + 5);
+
+        usage.record("laim:rows", None);
+        assert_eq!(usage.total(), 12 + 5);
+    }
+
+    #[test]
+    fn usage_ignores_other_apps_and_counts_utf16() {
+        let mut usage = Usage::default();
+        usage.record("rows", Some("12"));
+        usage.record("other:history", Some("[]"));
+        assert_eq!(usage.total(), 0);
+
+        usage.record("laim:name", Some("é𝄞"));
+        assert_eq!(usage.total(), 9 + 3);
+    }
+}
@@ -0,0 +1,62 @@
+use leptos::*;
+
+use crate::storage;
+
+const SEEN_KEY: &str = "seen_intro";
+
+const STEPS: &[(&str, &str)] = &[
+    (
+        "The grid",
+        "Click a black cell, or hover it and press any key, to score. Hitting anything else is a \
+         miss, and a miss ends the run once your lives run out.",
+    ),
+    (
+        "Settings",
+        "The ⚙ button at the top opens settings for the grid size, how many cells are lit at \
+         once, the mode and more. Changes are remembered between visits.",
+    ),
+    (
+        "History",
+        "Finished runs are listed below the grid with your score rate, alongside a calendar of \
+         the days you played. Press ? at any time for keyboard shortcuts.",
+    ),
+];
+
+/// A short first-visit walkthrough. It sits in a corner rather than over the grid, so the game
+/// stays playable while it is open, and never shows again once finished or skipped.
+#[component]
+pub fn Tour(cx: Scope) -> impl IntoView {
+    let (step, set_step) =
+        create_signal(cx, (!storage::get(SEEN_KEY).unwrap_or(false)).then_some(0));
+
+    let dismiss = move || {
+        set_step(None);
+        let _ = storage::set(SEEN_KEY, true);
+    };
+    let next = move |_| match step() {
+        Some(idx) if idx + 1 < STEPS.len() => set_step(Some(idx + 1)),
+        _ => dismiss(),
+    };
+
+    move || {
+        step().map(|idx| {
+            let (title, text) = STEPS[idx];
+            let last = idx + 1 == STEPS.len();
+            view! { cx,
+                <div
+                    class="Tour card"
+                    role="dialog"
+                    aria-label="Introduction"
+                >
+                    <strong>{title}</strong>
+                    <p>{text}</p>
+                    <div class="Tour buttons">
+                        <span>{format!("{}/{}", idx + 1, STEPS.len())}</span>
+                        <button on:click=move |_| dismiss()>"Skip"</button>
+                        <button on:click=next>{if last { "Done" } else { "Next" }}</button>
+                    </div>
+                </div>
+            }
+        })
+    }
+}